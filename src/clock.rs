@@ -0,0 +1,79 @@
+// Clock abstraction so tracking logic can be driven deterministically in tests
+//
+// `RouteTracker` otherwise calls `Instant::now()`/`SystemTime::now()`
+// directly in several places, which makes interval and status-expiry
+// logic impossible to exercise without racing real wall-clock time.
+
+use std::time::{Duration, Instant, SystemTime};
+
+/// A source of time for `RouteTracker`.
+///
+/// Swapping `RealClock` for `SimulatedClock` lets tests drive recording at
+/// exact simulated intervals and check timestamp math without sleeping.
+pub trait Clock: Send + Sync {
+    /// Current monotonic instant, analogous to `Instant::now()`.
+    fn now(&self) -> Instant;
+    /// Current wall-clock time, analogous to `SystemTime::now()`.
+    fn system_now(&self) -> SystemTime;
+}
+
+/// Wraps `Instant::now()`/`SystemTime::now()` for production use.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RealClock;
+
+impl Clock for RealClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn system_now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A manually-advanced clock for deterministic tests.
+///
+/// Time starts at the instant the clock is created and only moves forward
+/// when [`SimulatedClock::advance`] is called, so tests can assert on
+/// exact interval and status-expiry boundaries instead of racing real time.
+pub struct SimulatedClock {
+    base_instant: Instant,
+    base_system: SystemTime,
+    elapsed_ms: std::sync::atomic::AtomicU64,
+}
+
+impl SimulatedClock {
+    pub fn new() -> Self {
+        Self {
+            base_instant: Instant::now(),
+            base_system: SystemTime::now(),
+            elapsed_ms: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Move the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed_ms.fetch_add(
+            duration.as_millis() as u64,
+            std::sync::atomic::Ordering::SeqCst,
+        );
+    }
+}
+
+impl Default for SimulatedClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> Instant {
+        let elapsed_ms = self.elapsed_ms.load(std::sync::atomic::Ordering::SeqCst);
+        self.base_instant + Duration::from_millis(elapsed_ms)
+    }
+
+    fn system_now(&self) -> SystemTime {
+        let elapsed_ms = self.elapsed_ms.load(std::sync::atomic::Ordering::SeqCst);
+        self.base_system + Duration::from_millis(elapsed_ms)
+    }
+}
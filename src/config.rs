@@ -0,0 +1,185 @@
+// User-configurable settings, loaded from a TOML file placed next to the DLL.
+//
+// Every section mirrors a `[section]` table in that file. Fields added
+// after a section's initial release carry `#[serde(default)]` so existing
+// config files on disk keep loading without users having to edit them.
+
+use std::fs;
+use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Deserializer};
+use windows::Win32::Foundation::HINSTANCE;
+use windows::Win32::System::LibraryLoader::GetModuleFileNameW;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    VK_F1, VK_F2, VK_F3, VK_F4, VK_F5, VK_F6, VK_F7, VK_F8, VK_F9, VK_F10, VK_F11, VK_F12,
+};
+
+/// A keyboard key bound to an action.
+///
+/// Configured in TOML by name (e.g. `"F1"`) and resolved to a Win32
+/// virtual-key code once at load time.
+#[derive(Clone, Debug)]
+pub struct Key {
+    code: u32,
+    name: String,
+}
+
+impl Key {
+    fn from_name(name: &str) -> Result<Self, String> {
+        let code = match name.to_ascii_uppercase().as_str() {
+            "F1" => VK_F1.0 as u32,
+            "F2" => VK_F2.0 as u32,
+            "F3" => VK_F3.0 as u32,
+            "F4" => VK_F4.0 as u32,
+            "F5" => VK_F5.0 as u32,
+            "F6" => VK_F6.0 as u32,
+            "F7" => VK_F7.0 as u32,
+            "F8" => VK_F8.0 as u32,
+            "F9" => VK_F9.0 as u32,
+            "F10" => VK_F10.0 as u32,
+            "F11" => VK_F11.0 as u32,
+            "F12" => VK_F12.0 as u32,
+            other => return Err(format!("Unknown key name: {}", other)),
+        };
+        Ok(Self {
+            code,
+            name: name.to_string(),
+        })
+    }
+
+    /// Virtual-key code this binding resolves to.
+    pub fn virtual_key_code(&self) -> u32 {
+        self.code
+    }
+
+    /// The name this key was configured with, for logging.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        Key::from_name(&name).map_err(serde::de::Error::custom)
+    }
+}
+
+// =============================================================================
+// SECTIONS
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct KeybindingsConfig {
+    pub toggle_ui: Key,
+    pub toggle_recording: Key,
+    pub clear_route: Key,
+    pub save_route: Key,
+    /// Defaults to F9 so existing config files don't need to grow a new
+    /// key just to pick up the diagnostics overlay.
+    #[serde(default = "default_toggle_diagnostics_key")]
+    pub toggle_diagnostics: Key,
+}
+
+fn default_toggle_diagnostics_key() -> Key {
+    Key::from_name("F9").expect("F9 is a valid built-in key name")
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecordingConfig {
+    pub record_interval_ms: u64,
+    /// Which `RecordingMode`s are combined to decide when to record a
+    /// point. Defaults to interval-only, matching behavior before modes
+    /// were configurable.
+    #[serde(default = "default_recording_modes")]
+    pub modes: Vec<crate::tracker::RecordingMode>,
+    /// Minimum world-unit movement for `RecordingMode::Distance`.
+    #[serde(default = "default_min_distance")]
+    pub min_distance: f32,
+    /// Refuse to start recording when the custom pointer chains (Torrent
+    /// state, death count) aren't resolving. Off by default since it's a
+    /// stricter behavior than the original recording start had.
+    #[serde(default)]
+    pub strict_pointer_check: bool,
+}
+
+fn default_recording_modes() -> Vec<crate::tracker::RecordingMode> {
+    vec![crate::tracker::RecordingMode::Interval]
+}
+
+fn default_min_distance() -> f32 {
+    50.0
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OutputConfig {
+    pub routes_directory: String,
+    /// On-disk format for saved routes. Defaults to JSON, matching
+    /// behavior before Bincode support was added.
+    #[serde(default)]
+    pub format: crate::route::OutputFormat,
+}
+
+/// Embedded live position broadcast server, see [`crate::server::PositionServer`].
+#[derive(Debug, Deserialize)]
+pub struct ServerConfig {
+    /// Off by default: most users don't want a network listener running.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_bind_address")]
+    pub bind_address: String,
+}
+
+fn default_bind_address() -> String {
+    "127.0.0.1:7879".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub keybindings: KeybindingsConfig,
+    pub recording: RecordingConfig,
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_bind_address(),
+        }
+    }
+}
+
+impl Config {
+    /// Name of the config file expected next to the DLL.
+    pub const CONFIG_FILENAME: &'static str = "er_route_tracker.toml";
+
+    /// Directory the DLL was loaded from, used as the base for the config
+    /// file, saved routes, and the coordinate transformer CSV.
+    pub fn get_dll_directory(hmodule: HINSTANCE) -> Option<PathBuf> {
+        let mut buffer = [0u16; 260];
+        let len = unsafe { GetModuleFileNameW(hmodule, &mut buffer) } as usize;
+        if len == 0 {
+            return None;
+        }
+        let path = PathBuf::from(std::ffi::OsString::from_wide(&buffer[..len]));
+        path.parent().map(|p| p.to_path_buf())
+    }
+
+    /// Load and parse [`Config::CONFIG_FILENAME`] from the DLL's directory.
+    pub fn load(hmodule: HINSTANCE) -> Result<Self, String> {
+        let dir = Self::get_dll_directory(hmodule)
+            .ok_or_else(|| "Failed to determine DLL directory".to_string())?;
+        let path = dir.join(Self::CONFIG_FILENAME);
+
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&contents).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+}
@@ -3,6 +3,7 @@
 // These pointers were reverse-engineered from Cheat Engine tables
 // (eldenring_all-in-one_Hexinton-v5.0_ce7.5.ct)
 
+use hudhook::tracing::info;
 use libeldenring::memedit::PointerChain;
 use libeldenring::prelude::base_addresses::{BaseAddresses, Version};
 use libeldenring::version::get_version;
@@ -28,6 +29,67 @@ pub struct TorrentDebugInfo {
     pub is_inside_no_ride_area: Option<u8>,
 }
 
+/// Health snapshot of the custom pointer chains.
+///
+/// A chain reading `None` after the game has finished loading usually
+/// signals that its offset is stale for the current game `Version`.
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct PointerHealth {
+    pub ride_param_id_ok: bool,
+    pub is_riding_enabled_ok: bool,
+    pub riding_ok: bool,
+    pub is_it_a_horse_ok: bool,
+    pub horse_state_ok: bool,
+    pub horse_hp_ok: bool,
+    pub is_inside_no_ride_area_ok: bool,
+    pub death_count_ok: bool,
+}
+
+impl PointerHealth {
+    /// Derive a health snapshot from an already-read `TorrentDebugInfo`
+    /// and death count, instead of re-reading the pointer chains, so a
+    /// single diagnostic tick reads memory once and stays consistent.
+    pub fn from_reads(debug_info: &TorrentDebugInfo, death_count: Option<u32>) -> Self {
+        Self {
+            ride_param_id_ok: debug_info.ride_param_id.is_some(),
+            is_riding_enabled_ok: debug_info.is_riding_enabled.is_some(),
+            riding_ok: debug_info.riding.is_some(),
+            is_it_a_horse_ok: debug_info.is_it_a_horse.is_some(),
+            horse_state_ok: debug_info.horse_state.is_some(),
+            horse_hp_ok: debug_info.horse_hp.is_some(),
+            is_inside_no_ride_area_ok: debug_info.is_inside_no_ride_area.is_some(),
+            death_count_ok: death_count.is_some(),
+        }
+    }
+
+    /// True if every required chain resolved.
+    pub fn all_ok(&self) -> bool {
+        self.ride_param_id_ok
+            && self.is_riding_enabled_ok
+            && self.riding_ok
+            && self.is_it_a_horse_ok
+            && self.horse_state_ok
+            && self.horse_hp_ok
+            && self.is_inside_no_ride_area_ok
+            && self.death_count_ok
+    }
+
+    /// Names of the chains that failed to resolve, for actionable errors.
+    pub fn unhealthy_chains(&self) -> Vec<&'static str> {
+        let checks: [(bool, &'static str); 8] = [
+            (self.ride_param_id_ok, "ride_param_id"),
+            (self.is_riding_enabled_ok, "is_riding_enabled"),
+            (self.riding_ok, "riding"),
+            (self.is_it_a_horse_ok, "is_it_a_horse"),
+            (self.horse_state_ok, "horse_state"),
+            (self.horse_hp_ok, "horse_hp"),
+            (self.is_inside_no_ride_area_ok, "is_inside_no_ride_area"),
+            (self.death_count_ok, "death_count"),
+        ];
+        checks.into_iter().filter(|(ok, _)| !ok).map(|(_, name)| name).collect()
+    }
+}
+
 /// Custom pointers for route tracking features
 pub struct CustomPointers {
     // Ride module pointers (PlayerIns + 0x190 + 0xE8 + offset)
@@ -40,6 +102,8 @@ pub struct CustomPointers {
     is_inside_no_ride_area: PointerChain<u8>,
     // Death counter (GameDataMan + 0x94)
     death_count: PointerChain<u32>,
+    // Game version the pointer offsets above were selected for
+    version: Version,
 }
 
 impl CustomPointers {
@@ -57,6 +121,8 @@ impl CustomPointers {
 
         let world_chr_man = base_addresses.world_chr_man;
 
+        info!("Custom pointer offsets selected for game version {:?}", version);
+
         Self {
             // +0x190 +0xE8 +0x20
             ride_param_id: PointerChain::new(&[world_chr_man, player_ins, 0x190, 0xE8, 0x20]),
@@ -74,9 +140,24 @@ impl CustomPointers {
             is_inside_no_ride_area: PointerChain::new(&[world_chr_man, player_ins, 0x190, 0xE8, 0x164]),
             // GameDataMan + 0x94
             death_count: PointerChain::new(&[base_addresses.game_data_man, 0x94]),
+            version,
         }
     }
 
+    /// The game version these pointer offsets were selected for.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Validate that every pointer chain still resolves.
+    ///
+    /// Call this once the game has finished loading; a chain still
+    /// reading `None` at that point signals a broken offset on a new
+    /// patch rather than the game simply not being ready yet.
+    pub fn check_health(&self) -> PointerHealth {
+        PointerHealth::from_reads(&self.read_torrent_debug(), self.read_death_count())
+    }
+
     /// Read all Torrent-related debug values
     pub fn read_torrent_debug(&self) -> TorrentDebugInfo {
         TorrentDebugInfo {
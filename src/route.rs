@@ -1,17 +1,51 @@
 // Route data structures and serialization
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+
+use crate::clock::Clock;
 
 // =============================================================================
 // DATA STRUCTURES
 // =============================================================================
 
+/// On-disk serialization format for saved routes, selectable via
+/// `Config.output.format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Human-readable, larger files. Default.
+    #[default]
+    Json,
+    /// Compact binary encoding (bincode), for minute-resolution
+    /// multi-hour runs where pretty JSON gets unwieldy.
+    Bincode,
+}
+
+impl OutputFormat {
+    /// File extension used for this format (without the leading dot).
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Bincode => "bin",
+        }
+    }
+
+    /// Guess the format a route file was saved in from its extension, as
+    /// produced by [`save_route_to_file`]. Falls back to `Json` for an
+    /// unrecognized or missing extension.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("bin") => OutputFormat::Bincode,
+            _ => OutputFormat::Json,
+        }
+    }
+}
+
 /// Route point with timestamp (serializable)
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RoutePoint {
     /// Local X coordinate (within tile)
     pub x: f32,
@@ -33,10 +67,36 @@ pub struct RoutePoint {
     pub timestamp_ms: u64,
     /// Whether the player is riding Torrent
     pub on_torrent: bool,
+    /// Total deaths recorded so far in this run.
+    ///
+    /// Defaults to 0 when loading a route saved before this field
+    /// existed, so older PB files keep loading.
+    #[serde(default)]
+    pub death_count: u32,
+}
+
+/// Per-area timing and death stats for one contiguous stay on a map,
+/// derived from the recorded points in one pass at save time.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Segment {
+    /// Map tile ID for this segment
+    pub map_id: u32,
+    /// Map ID as human-readable string
+    pub map_id_str: String,
+    /// Timestamp of the first point in the segment
+    pub entry_timestamp_ms: u64,
+    /// Timestamp of the last point in the segment
+    pub exit_timestamp_ms: u64,
+    /// Euclidean distance traveled within the segment (global_x/global_z)
+    pub distance_traveled: f64,
+    /// Deaths that occurred while in this segment
+    pub deaths: u32,
+    /// Fraction of the segment's points spent riding Torrent (0.0-1.0)
+    pub torrent_fraction: f64,
 }
 
 /// Saved route file structure
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SavedRoute {
     /// Route name/description
     pub name: String,
@@ -50,6 +110,12 @@ pub struct SavedRoute {
     pub point_count: usize,
     /// The route points
     pub points: Vec<RoutePoint>,
+    /// Per-area splits derived from `points`.
+    ///
+    /// Defaults to empty when loading a route saved before segments
+    /// existed, so older PB files keep loading.
+    #[serde(default)]
+    pub segments: Vec<Segment>,
 }
 
 // =============================================================================
@@ -57,9 +123,10 @@ pub struct SavedRoute {
 // =============================================================================
 
 /// Simple timestamp generator (without chrono dependency)
-pub fn generate_timestamp() -> String {
-    let duration = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
+pub fn generate_timestamp(clock: &dyn Clock) -> String {
+    let duration = clock
+        .system_now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
         .unwrap_or_default();
     let secs = duration.as_secs();
     
@@ -77,38 +144,91 @@ pub fn generate_timestamp() -> String {
             years, months, day, hours, minutes, seconds)
 }
 
+// =============================================================================
+// SEGMENT DETECTION
+// =============================================================================
+
+/// Split the recorded points into per-map-area segments.
+///
+/// A new segment starts whenever `map_id` changes; splits are derived
+/// purely from the already-recorded points, in one pass at save time.
+fn compute_segments(points: &[RoutePoint]) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut start_idx = 0;
+
+    while start_idx < points.len() {
+        let map_id = points[start_idx].map_id;
+        let mut end_idx = start_idx;
+        while end_idx + 1 < points.len() && points[end_idx + 1].map_id == map_id {
+            end_idx += 1;
+        }
+
+        let segment_points = &points[start_idx..=end_idx];
+        let first = &segment_points[0];
+        let last = segment_points.last().unwrap_or(first);
+
+        let distance_traveled = segment_points.windows(2).map(|pair| {
+            let dx = (pair[1].global_x - pair[0].global_x) as f64;
+            let dz = (pair[1].global_z - pair[0].global_z) as f64;
+            (dx * dx + dz * dz).sqrt()
+        }).sum();
+
+        let torrent_points = segment_points.iter().filter(|p| p.on_torrent).count();
+
+        segments.push(Segment {
+            map_id,
+            map_id_str: first.map_id_str.clone(),
+            entry_timestamp_ms: first.timestamp_ms,
+            exit_timestamp_ms: last.timestamp_ms,
+            distance_traveled,
+            deaths: last.death_count.saturating_sub(first.death_count),
+            torrent_fraction: torrent_points as f64 / segment_points.len() as f64,
+        });
+
+        start_idx = end_idx + 1;
+    }
+
+    segments
+}
+
 // =============================================================================
 // ROUTE SAVING
 // =============================================================================
 
-/// Save a route to a JSON file
+/// Save a route to disk in the given format
 pub fn save_route_to_file(
     route: &[RoutePoint],
     base_dir: &PathBuf,
     routes_directory: &str,
     interval_ms: u64,
+    format: OutputFormat,
+    clock: &dyn Clock,
 ) -> Result<PathBuf, String> {
     if route.is_empty() {
         return Err("No route data to save".to_string());
     }
-    
+
     // Create routes directory
     let routes_dir = base_dir.join(routes_directory);
     if !routes_dir.exists() {
         fs::create_dir_all(&routes_dir)
             .map_err(|e| format!("Failed to create routes directory: {}", e))?;
     }
-    
+
     // Generate filename with timestamp
-    let now = generate_timestamp();
-    let filename = format!("route_{}.json", now.replace(":", "-").replace(" ", "_"));
+    let now = generate_timestamp(clock);
+    let filename = format!(
+        "route_{}.{}",
+        now.replace(":", "-").replace(" ", "_"),
+        format.extension()
+    );
     let filepath = routes_dir.join(&filename);
-    
+
     // Calculate total duration
     let duration_secs = route.last()
         .map(|p| p.timestamp_ms as f64 / 1000.0)
         .unwrap_or(0.0);
-    
+
     // Create saved route structure
     let saved_route = SavedRoute {
         name: format!("Route {}", now),
@@ -116,21 +236,65 @@ pub fn save_route_to_file(
         duration_secs,
         interval_ms,
         point_count: route.len(),
+        segments: compute_segments(route),
         points: route.to_vec(),
     };
-    
-    // Serialize to JSON
-    let json = serde_json::to_string_pretty(&saved_route)
-        .map_err(|e| format!("Failed to serialize route: {}", e))?;
-    
+
+    // Serialize in the requested format
+    let bytes: Vec<u8> = match format {
+        OutputFormat::Json => serde_json::to_vec_pretty(&saved_route)
+            .map_err(|e| format!("Failed to serialize route: {}", e))?,
+        OutputFormat::Bincode => bincode::serialize(&saved_route)
+            .map_err(|e| format!("Failed to serialize route: {}", e))?,
+    };
+
     // Write to file
     let mut file = File::create(&filepath)
         .map_err(|e| format!("Failed to create file: {}", e))?;
-    file.write_all(json.as_bytes())
+    file.write_all(&bytes)
         .map_err(|e| format!("Failed to write file: {}", e))?;
-    
+
     Ok(filepath)
 }
 
+/// Load a previously saved route from disk
+pub fn load_route_from_file(path: &Path, format: OutputFormat) -> Result<SavedRoute, String> {
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Failed to read route file: {}", e))?;
+
+    match format {
+        OutputFormat::Json => serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Failed to parse route JSON: {}", e)),
+        OutputFormat::Bincode => bincode::deserialize(&bytes)
+            .map_err(|e| format!("Failed to parse route bincode: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SimulatedClock;
+    use std::time::Duration;
+
+    #[test]
+    fn generate_timestamp_reflects_simulated_time() {
+        let clock = SimulatedClock::new();
+        let before = generate_timestamp(&clock);
+
+        // Advance by a whole day so the date part of the formatted string
+        // is forced to change; the time-of-day part stays the same.
+        clock.advance(Duration::from_secs(24 * 60 * 60));
+        let after = generate_timestamp(&clock);
+
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn generate_timestamp_is_frozen_between_calls_without_advancing() {
+        let clock = SimulatedClock::new();
+        assert_eq!(generate_timestamp(&clock), generate_timestamp(&clock));
+    }
+}
+
 
 
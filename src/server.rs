@@ -0,0 +1,186 @@
+// Live position broadcast server
+//
+// Streams the tracker's current position over a local HTTP listener so
+// external tools (LiveSplit-style overlays, web maps) can read the player
+// in real time without parsing the saved route files.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use hudhook::tracing::{info, warn};
+use serde::Serialize;
+
+// =============================================================================
+// BROADCAST FRAME
+// =============================================================================
+
+/// A single broadcast frame, sent as JSON to HTTP clients.
+///
+/// Mirrors the fields of `RoutePoint` that make sense outside the context
+/// of a recorded route (global position, map, Torrent/death state) and
+/// drops the per-tile local coordinates.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PositionFrame {
+    pub global_x: f32,
+    pub global_y: f32,
+    pub global_z: f32,
+    pub map_id: u32,
+    pub map_id_str: String,
+    pub on_torrent: bool,
+    pub death_count: Option<u32>,
+    pub timestamp_ms: u64,
+}
+
+type SharedFrame = Arc<RwLock<PositionFrame>>;
+
+// =============================================================================
+// SERVER
+// =============================================================================
+
+/// A local HTTP server exposing the tracker's current position.
+///
+/// Serves a one-shot `GET /position` for polling clients and a
+/// chunked-transfer `GET /position/stream` that pushes the latest frame
+/// published via [`PositionServer::broadcast`] to every subscriber once
+/// per 100 ms server tick, so external tools can subscribe instead of
+/// polling the saved JSON files. A frame is re-sent on each tick even if
+/// it has not changed, and frames replaced within one tick are never
+/// sent to stream clients. Runs on its own
+/// thread and shares state through an `Arc<RwLock<PositionFrame>>`; shuts
+/// down cleanly when dropped.
+pub struct PositionServer {
+    frame: SharedFrame,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PositionServer {
+    /// Bind `bind_address` and start serving on a dedicated thread.
+    pub fn start(bind_address: &str) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(bind_address)?;
+        listener.set_nonblocking(true)?;
+
+        let frame: SharedFrame = Arc::new(RwLock::new(PositionFrame::default()));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_frame = frame.clone();
+        let thread_running = running.clone();
+        let handle = thread::spawn(move || run_server(listener, thread_frame, thread_running));
+
+        info!("Position server listening on {}", bind_address);
+
+        Ok(Self {
+            frame,
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    /// Publish a new frame to polling and streaming clients.
+    pub fn broadcast(&self, frame: PositionFrame) {
+        if let Ok(mut guard) = self.frame.write() {
+            *guard = frame;
+        }
+    }
+}
+
+impl Drop for PositionServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn run_server(listener: TcpListener, frame: SharedFrame, running: Arc<AtomicBool>) {
+    let mut streaming_clients: Vec<TcpStream> = Vec::new();
+    let poll_interval = Duration::from_millis(100);
+    let (new_clients_tx, new_clients_rx) = mpsc::channel::<TcpStream>();
+
+    while running.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                // The request-line read below can block for up to 500ms
+                // (a stray probe, a paused client). Handling it inline
+                // here would stall this loop's periodic broadcast write
+                // to every already-subscribed `/position/stream` client
+                // for just as long, so hand each connection its own
+                // thread instead.
+                let new_client_frame = frame.clone();
+                let new_client_tx = new_clients_tx.clone();
+                thread::spawn(move || {
+                    if let Some(stream) = handle_connection(stream, &new_client_frame) {
+                        let _ = new_client_tx.send(stream);
+                    }
+                });
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(e) => warn!("Position server accept error: {}", e),
+        }
+
+        streaming_clients.extend(new_clients_rx.try_iter());
+
+        if !streaming_clients.is_empty() {
+            let body = frame
+                .read()
+                .ok()
+                .and_then(|f| serde_json::to_string(&*f).ok());
+            if let Some(body) = body {
+                let chunk = format!("{:x}\r\n{}\r\n", body.len(), body);
+                streaming_clients.retain_mut(|client| client.write_all(chunk.as_bytes()).is_ok());
+            }
+        }
+
+        thread::sleep(poll_interval);
+    }
+}
+
+/// Handle one accepted connection on its own thread: read the request
+/// line (bounded by a read timeout) and respond. Returns the stream back
+/// to the caller only for `/position/stream` requests, which the accept
+/// loop then folds into `streaming_clients`.
+fn handle_connection(mut stream: TcpStream, frame: &SharedFrame) -> Option<TcpStream> {
+    let _ = stream.set_nonblocking(false);
+    // A client that connects but never sends a request line must not be
+    // allowed to block this thread forever.
+    let _ = stream.set_read_timeout(Some(Duration::from_millis(500)));
+    let cloned = stream.try_clone().ok()?;
+    let mut reader = BufReader::new(cloned);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return None;
+    }
+
+    if request_line.starts_with("GET /position/stream") {
+        let header = "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/json\r\n\
+             Transfer-Encoding: chunked\r\n\
+             Connection: keep-alive\r\n\r\n";
+        if stream.write_all(header.as_bytes()).is_ok() && stream.set_nonblocking(true).is_ok() {
+            return Some(stream);
+        }
+        None
+    } else if request_line.starts_with("GET /position") {
+        let body = frame
+            .read()
+            .ok()
+            .and_then(|f| serde_json::to_string(&*f).ok())
+            .unwrap_or_else(|| "{}".to_string());
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes());
+        None
+    } else {
+        let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nConnection: close\r\n\r\n");
+        None
+    }
+}
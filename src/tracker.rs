@@ -1,16 +1,110 @@
 // Route Tracker - Main tracking logic
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use hudhook::tracing::{info, warn};
 use libeldenring::prelude::*;
+use serde::{Deserialize, Serialize};
 use windows::Win32::Foundation::HINSTANCE;
 
+use crate::clock::{Clock, RealClock};
 use crate::config::Config;
 use crate::coordinate_transformer::WorldPositionTransformer;
-use crate::custom_pointers::CustomPointers;
-use crate::route::{save_route_to_file, RoutePoint};
+use crate::custom_pointers::{CustomPointers, PointerHealth, TorrentDebugInfo};
+use crate::route::{load_route_from_file, save_route_to_file, OutputFormat, RoutePoint};
+use crate::server::{PositionFrame, PositionServer};
+
+// =============================================================================
+// RECORDING MODE
+// =============================================================================
+
+/// Which triggers cause `record_position` to append a new point.
+///
+/// Modes are combinable through `Config.recording.modes`: e.g. `Interval`
+/// plus `Event` keeps the time-based floor while guaranteeing that a map
+/// transition, a Torrent mount/dismount, or a death is never dropped in
+/// the middle of a long interval gap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingMode {
+    /// Record when `record_interval` has elapsed since the last point.
+    Interval,
+    /// Record once the player has moved more than `min_distance` world
+    /// units (euclidean, on `global_x`/`global_z`) since the last point.
+    Distance,
+    /// Record on map-id change, Torrent mount/dismount, or a death.
+    Event,
+}
+
+/// How long a status message set via `set_status` stays visible.
+const STATUS_MESSAGE_TTL: Duration = Duration::from_secs(3);
+
+/// Pure time-comparison helpers, pulled out of `RouteTracker` methods so
+/// they can be unit-tested directly against `Instant`s produced by a
+/// `SimulatedClock` instead of requiring a full `RouteTracker`.
+fn status_is_valid(set_at: Instant, now: Instant, ttl: Duration) -> bool {
+    now.duration_since(set_at) < ttl
+}
+
+fn interval_elapsed(last_record_time: Instant, now: Instant, interval: Duration) -> bool {
+    now.duration_since(last_record_time) >= interval
+}
+
+// =============================================================================
+// REFERENCE ROUTE (PB COMPARISON)
+// =============================================================================
+
+/// A loaded reference route (e.g. a personal best) used to compute a
+/// running time delta against the live recording.
+///
+/// Nearest-point matching uses a monotonic cursor: each lookup only scans
+/// a small window around the last match instead of the whole route, which
+/// keeps it O(1) amortized as the run progresses.
+pub(crate) struct ReferenceRoute {
+    points: Vec<RoutePoint>,
+    cursor: usize,
+}
+
+impl ReferenceRoute {
+    const SEARCH_BACKWARD: usize = 5;
+    const SEARCH_FORWARD: usize = 200;
+
+    fn load(path: &Path) -> Result<Self, String> {
+        let saved = load_route_from_file(path, OutputFormat::from_path(path))?;
+        Ok(Self {
+            points: saved.points,
+            cursor: 0,
+        })
+    }
+
+    /// Find the reference point nearest to `(global_x, global_z)`,
+    /// searching the window around the last match and advancing the
+    /// cursor to the new match.
+    fn nearest(&mut self, global_x: f32, global_z: f32) -> Option<&RoutePoint> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        let start = self.cursor.saturating_sub(Self::SEARCH_BACKWARD);
+        let end = (self.cursor + Self::SEARCH_FORWARD).min(self.points.len() - 1);
+
+        let mut best_index = start;
+        let mut best_dist_sq = f32::MAX;
+        for (i, point) in self.points.iter().enumerate().take(end + 1).skip(start) {
+            let dx = point.global_x - global_x;
+            let dz = point.global_z - global_z;
+            let dist_sq = dx * dx + dz * dz;
+            if dist_sq < best_dist_sq {
+                best_dist_sq = dist_sq;
+                best_index = i;
+            }
+        }
+
+        self.cursor = best_index;
+        self.points.get(best_index)
+    }
+}
 
 // =============================================================================
 // ROUTE TRACKER
@@ -30,11 +124,42 @@ pub struct RouteTracker {
     pub(crate) base_dir: PathBuf,
     pub(crate) status_message: Option<(String, Instant)>,
     pub(crate) transformer: WorldPositionTransformer,
+    /// Embedded local server broadcasting live position, gated behind
+    /// `Config.server.enabled` so users who don't want a network listener
+    /// pay nothing.
+    pub(crate) position_server: Option<PositionServer>,
+    /// Global position at the last recorded point, for `RecordingMode::Distance`.
+    pub(crate) last_recorded_global_xz: Option<(f32, f32)>,
+    /// Map id at the last recorded point, for `RecordingMode::Event`.
+    pub(crate) last_map_id: Option<u32>,
+    /// Torrent state at the last recorded point, for `RecordingMode::Event`.
+    pub(crate) last_on_torrent: Option<bool>,
+    /// Death count at the last recorded point, for `RecordingMode::Event`.
+    pub(crate) last_death_count: Option<u32>,
+    /// Reference route (e.g. a personal best) loaded for live comparison.
+    pub(crate) reference_route: Option<ReferenceRoute>,
+    /// Time source driving intervals, status expiry, and timestamp
+    /// generation. `RealClock` in production; swappable for a
+    /// `SimulatedClock` in tests via [`RouteTracker::with_clock`].
+    pub(crate) clock: Box<dyn Clock>,
+    /// Whether the pointer/Torrent diagnostic overlay is active, toggled
+    /// via `Config.keybindings.toggle_diagnostics`.
+    pub(crate) diagnostics_enabled: bool,
+    /// Latest diagnostic snapshot, refreshed by `update_diagnostics`.
+    pub(crate) last_diagnostics: Option<(TorrentDebugInfo, Option<u32>, PointerHealth)>,
 }
 
 impl RouteTracker {
     /// Create a new RouteTracker instance
     pub fn new(hmodule: HINSTANCE) -> Option<Self> {
+        Self::with_clock(hmodule, Box::new(RealClock))
+    }
+
+    /// Create a new RouteTracker instance with an injected `Clock`.
+    ///
+    /// Exists so tests can drive recording with a `SimulatedClock` instead
+    /// of real wall-clock time; `new` always uses `RealClock`.
+    pub fn with_clock(hmodule: HINSTANCE, clock: Box<dyn Clock>) -> Option<Self> {
         info!("Initializing Route Tracker...");
         
         // Load configuration - REQUIRED (from DLL directory)
@@ -50,11 +175,12 @@ impl RouteTracker {
             }
         };
         
-        info!("Keybindings: Toggle UI={}, Toggle Recording={}, Clear={}, Save={}",
+        info!("Keybindings: Toggle UI={}, Toggle Recording={}, Clear={}, Save={}, Toggle Diagnostics={}",
             config.keybindings.toggle_ui.name(),
             config.keybindings.toggle_recording.name(),
             config.keybindings.clear_route.name(),
-            config.keybindings.save_route.name()
+            config.keybindings.save_route.name(),
+            config.keybindings.toggle_diagnostics.name()
         );
         
         // Get the DLL's directory for saving routes
@@ -97,44 +223,110 @@ impl RouteTracker {
         info!("Route Tracker initialized!");
         
         let record_interval = Duration::from_millis(config.recording.record_interval_ms);
-        
+
+        let position_server = if config.server.enabled {
+            match PositionServer::start(&config.server.bind_address) {
+                Ok(server) => Some(server),
+                Err(e) => {
+                    warn!("Failed to start position server: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         Some(Self {
             pointers,
             custom_pointers,
             route: Vec::new(),
             is_recording: false,
             start_time: None,
-            last_record_time: Instant::now(),
+            last_record_time: clock.now(),
             record_interval,
             show_ui: true,
             config,
             base_dir,
             status_message: None,
             transformer,
+            position_server,
+            last_recorded_global_xz: None,
+            last_map_id: None,
+            last_on_torrent: None,
+            last_death_count: None,
+            reference_route: None,
+            clock,
+            diagnostics_enabled: false,
+            last_diagnostics: None,
         })
     }
-    
+
     /// Start recording
-    pub fn start_recording(&mut self) {
+    pub fn start_recording(&mut self) -> Result<(), String> {
+        if self.config.recording.strict_pointer_check {
+            let health = self.custom_pointers.check_health();
+            if !health.all_ok() {
+                let message = format!(
+                    "Refusing to start recording: pointer chains unhealthy for {:?} ({}). \
+                     This usually means the game patch isn't supported yet.",
+                    self.custom_pointers.version(),
+                    health.unhealthy_chains().join(", ")
+                );
+                warn!("{}", message);
+                return Err(message);
+            }
+        }
+
         self.route.clear();
-        self.start_time = Some(Instant::now());
+        self.start_time = Some(self.clock.now());
         self.is_recording = true;
-        info!("Recording started!");
+        self.last_recorded_global_xz = None;
+        self.last_map_id = None;
+        self.last_on_torrent = None;
+        self.last_death_count = None;
+        info!("Recording started! (pointer offsets for {:?})", self.custom_pointers.version());
+        Ok(())
     }
-    
+
     /// Stop recording
     pub fn stop_recording(&mut self) {
         self.is_recording = false;
         info!("Recording stopped! {} points recorded.", self.route.len());
     }
-    
-    /// Record current position if the interval has elapsed
-    pub fn record_position(&mut self) {
-        if !self.is_recording {
+
+    /// Toggle the pointer/Torrent diagnostic overlay, bound to
+    /// `Config.keybindings.toggle_diagnostics`.
+    pub fn toggle_diagnostics(&mut self) {
+        self.diagnostics_enabled = !self.diagnostics_enabled;
+        info!("Diagnostics {}", if self.diagnostics_enabled { "enabled" } else { "disabled" });
+    }
+
+    /// Refresh the diagnostic snapshot if diagnostics are enabled.
+    ///
+    /// Continuously reads `TorrentDebugInfo`, the death count, and the
+    /// pointer chain health so a broken offset shows up immediately after
+    /// a new patch, instead of silently producing an empty route.
+    pub fn update_diagnostics(&mut self) {
+        if !self.diagnostics_enabled {
             return;
         }
 
-        if self.last_record_time.elapsed() < self.record_interval {
+        let debug_info = self.custom_pointers.read_torrent_debug();
+        let death_count = self.custom_pointers.read_death_count();
+        let health = PointerHealth::from_reads(&debug_info, death_count);
+        self.last_diagnostics = Some((debug_info, death_count, health));
+    }
+
+    /// Latest diagnostic snapshot, if diagnostics are enabled.
+    pub fn get_diagnostics(&self) -> Option<&(TorrentDebugInfo, Option<u32>, PointerHealth)> {
+        self.last_diagnostics.as_ref().filter(|_| self.diagnostics_enabled)
+    }
+
+    /// Record current position if any enabled `RecordingMode` fires
+    pub fn record_position(&mut self) {
+        self.broadcast_position();
+
+        if !self.is_recording {
             return;
         }
 
@@ -142,10 +334,6 @@ impl RouteTracker {
             self.pointers.global_position.read(),
             self.pointers.global_position.read_map_id(),
         ) {
-            let timestamp_ms = self.start_time
-                .map(|t| t.elapsed().as_millis() as u64)
-                .unwrap_or(0);
-
             // Convert to global coordinates
             let (global_x, global_y, global_z) = self.transformer
                 .local_to_world_first(map_id, x, y, z)
@@ -156,6 +344,21 @@ impl RouteTracker {
             // Detect if player is riding Torrent
             let on_torrent = self.custom_pointers.is_on_torrent();
 
+            // Carry the last known death count forward on a failed read,
+            // since it's a monotonically increasing counter.
+            let death_count = self.custom_pointers.read_death_count()
+                .unwrap_or_else(|| self.last_death_count.unwrap_or(0));
+
+            let timestamp_ms = self.start_time
+                .map(|t| self.clock.now().duration_since(t).as_millis() as u64)
+                .unwrap_or(0);
+
+            self.compare_to_reference(global_x, global_z, timestamp_ms);
+
+            if !self.should_record(map_id, on_torrent, death_count, global_x, global_z) {
+                return;
+            }
+
             self.route.push(RoutePoint {
                 x,
                 y,
@@ -167,37 +370,160 @@ impl RouteTracker {
                 map_id_str,
                 timestamp_ms,
                 on_torrent,
+                death_count,
             });
 
-            self.last_record_time = Instant::now();
+            self.last_record_time = self.clock.now();
+            self.last_recorded_global_xz = Some((global_x, global_z));
+            self.last_map_id = Some(map_id);
+            self.last_on_torrent = Some(on_torrent);
+            self.last_death_count = Some(death_count);
         }
     }
-    
-    /// Save the recorded route to a JSON file
+
+    /// Decide whether the current sample satisfies any `RecordingMode`
+    /// enabled in `Config.recording.modes`.
+    fn should_record(
+        &self,
+        map_id: u32,
+        on_torrent: bool,
+        death_count: u32,
+        global_x: f32,
+        global_z: f32,
+    ) -> bool {
+        let modes = &self.config.recording.modes;
+
+        if modes.contains(&RecordingMode::Interval)
+            && interval_elapsed(self.last_record_time, self.clock.now(), self.record_interval)
+        {
+            return true;
+        }
+
+        if modes.contains(&RecordingMode::Distance) {
+            let moved_far_enough = match self.last_recorded_global_xz {
+                Some((lx, lz)) => {
+                    let dx = global_x - lx;
+                    let dz = global_z - lz;
+                    (dx * dx + dz * dz).sqrt() > self.config.recording.min_distance
+                }
+                None => true,
+            };
+            if moved_far_enough {
+                return true;
+            }
+        }
+
+        if modes.contains(&RecordingMode::Event) {
+            let map_changed = self.last_map_id.map(|m| m != map_id).unwrap_or(true);
+            let torrent_changed = self.last_on_torrent.map(|t| t != on_torrent).unwrap_or(true);
+            let died = self.last_death_count.map(|last| death_count > last).unwrap_or(false);
+            if map_changed || torrent_changed || died {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Push the current position to the position server, if enabled.
+    ///
+    /// Runs independently of `is_recording` so overlays get a live feed of
+    /// the player even when no route is being captured.
+    fn broadcast_position(&self) {
+        let Some(server) = &self.position_server else {
+            return;
+        };
+
+        if let (Some([x, y, z, _, _]), Some(map_id)) = (
+            self.pointers.global_position.read(),
+            self.pointers.global_position.read_map_id(),
+        ) {
+            let timestamp_ms = self.start_time
+                .map(|t| self.clock.now().duration_since(t).as_millis() as u64)
+                .unwrap_or(0);
+
+            let (global_x, global_y, global_z) = self.transformer
+                .local_to_world_first(map_id, x, y, z)
+                .unwrap_or((x, y, z));
+
+            server.broadcast(PositionFrame {
+                global_x,
+                global_y,
+                global_z,
+                map_id,
+                map_id_str: WorldPositionTransformer::format_map_id(map_id),
+                on_torrent: self.custom_pointers.is_on_torrent(),
+                death_count: self.custom_pointers.read_death_count(),
+                timestamp_ms,
+            });
+        }
+    }
+
+    /// Save the recorded route to disk, in the format configured in
+    /// `Config.output.format`
     pub fn save_route(&self) -> Result<PathBuf, String> {
         let result = save_route_to_file(
             &self.route,
             &self.base_dir,
             &self.config.output.routes_directory,
             self.config.recording.record_interval_ms,
+            self.config.output.format,
+            self.clock.as_ref(),
         );
-        
+
         if let Ok(ref path) = result {
             info!("Route saved to: {}", path.display());
         }
-        
+
         result
     }
+
+    /// Load a reference route (e.g. a personal best) to compare against
+    /// while recording. Subsequent calls to `record_position` will report
+    /// the running time delta via `set_status`/`get_status`.
+    ///
+    /// The reference file's format is sniffed from its extension rather
+    /// than read from `Config.output.format`, since that setting controls
+    /// the *live* recording's save format and may differ from whatever
+    /// format this reference file was originally saved in.
+    pub fn load_reference_route(&mut self, path: &Path) -> Result<(), String> {
+        let reference = ReferenceRoute::load(path)?;
+        info!("Loaded reference route from {:?} ({} points)", path, reference.points.len());
+        self.reference_route = Some(reference);
+        Ok(())
+    }
+
+    /// Compare the current position against the loaded reference route
+    /// and report the running ahead/behind delta via `set_status`.
+    fn compare_to_reference(&mut self, global_x: f32, global_z: f32, timestamp_ms: u64) {
+        let message = match &mut self.reference_route {
+            Some(reference) => reference.nearest(global_x, global_z).map(|point| {
+                let delta_ms = timestamp_ms as i64 - point.timestamp_ms as i64;
+                let delta_secs = delta_ms as f64 / 1000.0;
+                if delta_ms <= 0 {
+                    format!("PB: {:.1}s ahead", -delta_secs)
+                } else {
+                    format!("PB: {:.1}s behind", delta_secs)
+                }
+            }),
+            None => None,
+        };
+
+        if let Some(message) = message {
+            self.set_status(message);
+        }
+    }
     
     /// Set a status message that will be displayed temporarily
     pub fn set_status(&mut self, message: String) {
-        self.status_message = Some((message, Instant::now()));
+        self.status_message = Some((message, self.clock.now()));
     }
-    
+
     /// Get current status message if still valid (within 3 seconds)
     pub fn get_status(&self) -> Option<&str> {
+        let now = self.clock.now();
         self.status_message.as_ref().and_then(|(msg, time)| {
-            if time.elapsed() < Duration::from_secs(3) {
+            if status_is_valid(*time, now, STATUS_MESSAGE_TTL) {
                 Some(msg.as_str())
             } else {
                 None
@@ -224,5 +550,68 @@ impl RouteTracker {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SimulatedClock;
+
+    #[test]
+    fn status_message_valid_before_ttl() {
+        let clock = SimulatedClock::new();
+        let set_at = clock.now();
+
+        clock.advance(Duration::from_millis(2999));
+        assert!(status_is_valid(set_at, clock.now(), STATUS_MESSAGE_TTL));
+    }
+
+    #[test]
+    fn status_message_expires_exactly_at_ttl() {
+        let clock = SimulatedClock::new();
+        let set_at = clock.now();
+
+        clock.advance(Duration::from_millis(3000));
+        assert!(!status_is_valid(set_at, clock.now(), STATUS_MESSAGE_TTL));
+    }
+
+    #[test]
+    fn status_message_expires_after_ttl() {
+        let clock = SimulatedClock::new();
+        let set_at = clock.now();
+
+        clock.advance(Duration::from_millis(3001));
+        assert!(!status_is_valid(set_at, clock.now(), STATUS_MESSAGE_TTL));
+    }
+
+    #[test]
+    fn interval_not_elapsed_before_record_interval() {
+        let clock = SimulatedClock::new();
+        let last_record_time = clock.now();
+        let interval = Duration::from_millis(500);
+
+        clock.advance(Duration::from_millis(499));
+        assert!(!interval_elapsed(last_record_time, clock.now(), interval));
+    }
+
+    #[test]
+    fn interval_elapsed_at_exact_tick() {
+        let clock = SimulatedClock::new();
+        let last_record_time = clock.now();
+        let interval = Duration::from_millis(500);
+
+        clock.advance(Duration::from_millis(500));
+        assert!(interval_elapsed(last_record_time, clock.now(), interval));
+    }
+
+    #[test]
+    fn interval_elapsed_after_tick() {
+        let clock = SimulatedClock::new();
+        let last_record_time = clock.now();
+        let interval = Duration::from_millis(500);
+
+        clock.advance(Duration::from_millis(750));
+        assert!(interval_elapsed(last_record_time, clock.now(), interval));
+    }
+}
+
 
 